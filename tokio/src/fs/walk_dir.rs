@@ -0,0 +1,219 @@
+use crate::fs::{asyncify, read_dir, DirEntry, ReadDir};
+
+use futures_core::ready;
+use futures_core::stream::Stream;
+use std::fs::FileType;
+use std::future::Future;
+use std::io;
+use std::mem;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::task::Context;
+use std::task::Poll;
+
+/// Recursively walk a directory tree, asynchronously.
+///
+/// This builds on [`read_dir`] and [`DirEntry`]: descending into a
+/// subdirectory just opens another [`ReadDir`] and pushes it onto an
+/// internal stack, so the walk is driven lazily, one blocking-pool
+/// round trip at a time, as the returned stream is polled.
+///
+/// By default the whole tree is walked and symlinks are yielded but not
+/// followed; see [`WalkDir::max_depth`], [`WalkDir::min_depth`] and
+/// [`WalkDir::follow_symlinks`] to change that.
+pub async fn walk_dir(path: impl AsRef<Path>) -> io::Result<WalkDir> {
+    let root = read_dir(path).await?;
+
+    Ok(WalkDir {
+        stack: vec![(root, 0)],
+        state: State::Next,
+        pending_yield: None,
+        max_depth: None,
+        min_depth: 0,
+        follow_symlinks: false,
+    })
+}
+
+/// Stream of the entries within a directory tree, returned by [`walk_dir`].
+///
+/// # Errors
+///
+/// Like [`ReadDir`], this stream yields an [`Err`] for any entry that can't
+/// be read or inspected, without aborting the rest of the walk.
+#[must_use = "streams do nothing unless polled"]
+pub struct WalkDir {
+    /// One `ReadDir` per directory currently being descended into, paired
+    /// with its depth relative to the root. The last element is the one
+    /// currently being drained.
+    stack: Vec<(ReadDir, usize)>,
+    state: State,
+    /// An entry that has already been produced and is waiting to be
+    /// returned from the next call to `poll_next`, once any bookkeeping
+    /// needed to keep walking (e.g. descending into it) has been set up.
+    pending_yield: Option<WalkEntry>,
+    max_depth: Option<usize>,
+    min_depth: usize,
+    follow_symlinks: bool,
+}
+
+type BoxFuture<T> = Pin<Box<dyn Future<Output = io::Result<T>> + Send>>;
+
+enum State {
+    /// Pull the next entry from the directory at the top of the stack.
+    Next,
+    /// Waiting on an entry's file type (or, with `follow_symlinks`, its
+    /// metadata) to decide whether to descend into it.
+    CheckType { depth: usize, fut: BoxFuture<(DirEntry, FileType)> },
+    /// Waiting on `read_dir` for a subdirectory this walk is descending
+    /// into.
+    Descend { next_depth: usize, fut: BoxFuture<ReadDir> },
+}
+
+/// An entry yielded by [`WalkDir`], paired with its depth relative to the
+/// root passed to [`walk_dir`] (which is depth `0`).
+#[derive(Debug)]
+pub struct WalkEntry {
+    entry: DirEntry,
+    depth: usize,
+}
+
+impl WalkEntry {
+    /// This entry's depth relative to the walk's root.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Unwrap this `WalkEntry`, discarding the depth.
+    pub fn into_entry(self) -> DirEntry {
+        self.entry
+    }
+}
+
+impl Deref for WalkEntry {
+    type Target = DirEntry;
+
+    fn deref(&self) -> &DirEntry {
+        &self.entry
+    }
+}
+
+impl WalkDir {
+    /// Limit how many levels below the root this walk descends into.
+    ///
+    /// A `max_depth` of `0` only yields entries directly inside the root.
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    /// Only yield entries at or below this depth.
+    ///
+    /// Shallower entries are still traversed, so that their children are
+    /// reached, but are not themselves yielded from the stream.
+    pub fn min_depth(mut self, min_depth: usize) -> Self {
+        self.min_depth = min_depth;
+        self
+    }
+
+    /// Follow symlinks when deciding whether to descend into an entry.
+    ///
+    /// By default, a symlink is yielded like any other entry but never
+    /// traversed, even if it points at a directory, mirroring
+    /// [`DirEntry::file_type`]'s own behavior. With this enabled, `WalkDir`
+    /// instead looks up the metadata of the entry's path, which follows
+    /// symlinks, to decide whether to descend.
+    pub fn follow_symlinks(mut self, follow_symlinks: bool) -> Self {
+        self.follow_symlinks = follow_symlinks;
+        self
+    }
+}
+
+impl Stream for WalkDir {
+    type Item = io::Result<WalkEntry>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        loop {
+            if let Some(entry) = this.pending_yield.take() {
+                return Poll::Ready(Some(Ok(entry)));
+            }
+
+            match mem::replace(&mut this.state, State::Next) {
+                State::Next => {
+                    let depth = match this.stack.last() {
+                        Some((_, depth)) => *depth,
+                        None => return Poll::Ready(None),
+                    };
+
+                    let top = &mut this.stack.last_mut().unwrap().0;
+                    let polled = Pin::new(top).poll_next(cx);
+
+                    this.state = match ready!(polled) {
+                        None => {
+                            this.stack.pop();
+                            State::Next
+                        }
+                        Some(Err(e)) => return Poll::Ready(Some(Err(e))),
+                        Some(Ok(entry)) => {
+                            let follow_symlinks = this.follow_symlinks;
+                            let fut: BoxFuture<(DirEntry, FileType)> = if follow_symlinks {
+                                Box::pin(async move {
+                                    let path = entry.path();
+                                    let metadata = asyncify(move || std::fs::metadata(path)).await?;
+                                    let file_type = metadata.file_type();
+                                    Ok((entry, file_type))
+                                })
+                            } else {
+                                Box::pin(async move {
+                                    let file_type = entry.file_type().await?;
+                                    Ok((entry, file_type))
+                                })
+                            };
+
+                            State::CheckType { depth, fut }
+                        }
+                    };
+                }
+                State::CheckType { depth, mut fut } => match fut.as_mut().poll(cx) {
+                    Poll::Pending => {
+                        this.state = State::CheckType { depth, fut };
+                        return Poll::Pending;
+                    }
+                    Poll::Ready(Err(e)) => return Poll::Ready(Some(Err(e))),
+                    Poll::Ready(Ok((entry, file_type))) => {
+                        let should_descend = file_type.is_dir()
+                            && this.max_depth.map_or(true, |max| depth < max);
+
+                        let descend_path: Option<PathBuf> =
+                            if should_descend { Some(entry.path()) } else { None };
+
+                        if depth >= this.min_depth {
+                            this.pending_yield = Some(WalkEntry { entry, depth });
+                        }
+
+                        this.state = match descend_path {
+                            Some(path) => State::Descend {
+                                next_depth: depth + 1,
+                                fut: Box::pin(read_dir(path)),
+                            },
+                            None => State::Next,
+                        };
+                    }
+                },
+                State::Descend { next_depth, mut fut } => match fut.as_mut().poll(cx) {
+                    Poll::Pending => {
+                        this.state = State::Descend { next_depth, fut };
+                        return Poll::Pending;
+                    }
+                    Poll::Ready(Err(e)) => return Poll::Ready(Some(Err(e))),
+                    Poll::Ready(Ok(child)) => {
+                        this.stack.push((child, next_depth));
+                        this.state = State::Next;
+                    }
+                },
+            }
+        }
+    }
+}
@@ -0,0 +1,166 @@
+//! Builder for configuring and constructing a [`Runtime`](crate::runtime::Runtime).
+
+use crate::runtime::Kind;
+use crate::runtime::Runtime;
+use crate::runtime::{io, timer};
+
+#[cfg(feature = "blocking")]
+use crate::runtime::blocking;
+#[cfg(feature = "rt-current-thread")]
+use crate::runtime::current_thread::CurrentThread;
+#[cfg(feature = "rt-full")]
+use crate::runtime::thread_pool::ThreadPool;
+#[cfg(feature = "blocking")]
+use crate::runtime::Park;
+
+use std::io as stdio;
+#[cfg(feature = "blocking")]
+use std::sync::Arc;
+
+/// Builds a Tokio [`Runtime`](crate::runtime::Runtime) with custom
+/// configuration values.
+///
+/// Methods can be chained in order to set the configuration values. The
+/// `Runtime` is constructed by calling [`build`](Builder::build).
+///
+/// New instances of `Builder` are obtained via [`Builder::new`].
+///
+/// See function level documentation for details on the various
+/// configuration settings.
+///
+/// # Examples
+///
+/// ```
+/// use tokio::runtime::Builder;
+///
+/// # async fn dox() -> std::io::Result<()> {
+/// let rt = Builder::new()
+///     .thread_pool()
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct Builder {
+    /// The kind of executor the built `Runtime` will use.
+    scheduler: Scheduler,
+
+    /// Factory for the `Park` implementation handed to each worker thread
+    /// (or the single current-thread executor).
+    ///
+    /// Defaults to a platform parker backed by a condition variable (or Mio,
+    /// when the `net-driver` feature is enabled). Overriding it is what lets
+    /// callers plug in a deterministic or instrumented parker for
+    /// simulation / deterministic testing, the same mechanism the `pool!`
+    /// test macro already uses internally via `MockPark`.
+    #[cfg(feature = "blocking")]
+    park_factory: Option<Arc<dyn Fn(usize) -> Box<dyn Park> + Send + Sync>>,
+}
+
+#[derive(Clone, Copy)]
+enum Scheduler {
+    Shell,
+    #[cfg(feature = "rt-current-thread")]
+    CurrentThread,
+    #[cfg(feature = "rt-full")]
+    ThreadPool,
+}
+
+impl Builder {
+    /// Returns a new builder with the default configuration values.
+    pub fn new() -> Builder {
+        Builder {
+            scheduler: Scheduler::Shell,
+            #[cfg(feature = "blocking")]
+            park_factory: None,
+        }
+    }
+
+    /// Use a multi-threaded, work-stealing thread pool to execute tasks.
+    #[cfg(feature = "rt-full")]
+    pub fn thread_pool(&mut self) -> &mut Self {
+        self.scheduler = Scheduler::ThreadPool;
+        self
+    }
+
+    /// Run all tasks on the current thread instead of spawning a thread
+    /// pool.
+    #[cfg(feature = "rt-current-thread")]
+    pub fn current_thread(&mut self) -> &mut Self {
+        self.scheduler = Scheduler::CurrentThread;
+        self
+    }
+
+    /// Supply a factory used to construct the [`Park`] instance each worker
+    /// thread parks on while it has no work, instead of the default
+    /// platform parker.
+    ///
+    /// The factory is called once per worker thread (or once, for a
+    /// current-thread runtime) with that worker's index, mirroring the
+    /// signature `pool!`'s internal `MockPark::mk_park` already has. This
+    /// turns that previously-internal mocking mechanism into a supported
+    /// extension point: simulation frameworks and integration tests can
+    /// supply a deterministic or instrumented parker instead of the real
+    /// OS-backed one.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tokio::runtime::Builder;
+    ///
+    /// # fn make_park(_index: usize) -> Box<dyn tokio::runtime::Park> { unimplemented!() }
+    /// let rt = Builder::new()
+    ///     .thread_pool()
+    ///     .park_factory(make_park)
+    ///     .build();
+    /// ```
+    #[cfg(feature = "blocking")]
+    pub fn park_factory<F>(&mut self, f: F) -> &mut Self
+    where
+        F: Fn(usize) -> Box<dyn Park> + Send + Sync + 'static,
+    {
+        self.park_factory = Some(Arc::new(f));
+        self
+    }
+
+    /// Create the configured `Runtime`.
+    #[cfg(feature = "blocking")]
+    pub fn build(&mut self) -> stdio::Result<Runtime> {
+        let (net_driver, net_handle) = io::create()?;
+        let (timer_driver, timer_handle) = timer::create(net_driver);
+
+        let blocking_pool = blocking::Pool::new("tokio-blocking".into(), None);
+        let blocking_waiter = blocking_pool.waiter();
+
+        let kind = match self.scheduler {
+            Scheduler::Shell => Kind::Shell,
+            #[cfg(feature = "rt-current-thread")]
+            Scheduler::CurrentThread => {
+                Kind::CurrentThread(CurrentThread::new(timer_driver, self.park_factory.clone()))
+            }
+            #[cfg(feature = "rt-full")]
+            Scheduler::ThreadPool => Kind::ThreadPool(ThreadPool::new(
+                num_cpus(),
+                self.park_factory.clone(),
+                blocking_pool,
+            )),
+        };
+
+        Ok(Runtime {
+            kind,
+            net_handles: vec![net_handle],
+            timer_handles: vec![timer_handle],
+            blocking_pool: blocking_waiter,
+        })
+    }
+}
+
+impl Default for Builder {
+    fn default() -> Self {
+        Builder::new()
+    }
+}
+
+#[cfg(feature = "rt-full")]
+fn num_cpus() -> usize {
+    std::cmp::max(1, num_cpus::get())
+}
@@ -0,0 +1,259 @@
+//! A single-threaded task set for running `!Send` futures.
+//!
+//! The regular [`spawn`] function requires the spawned future to be `Send`
+//! because it may be moved onto another worker thread. [`LocalSet`] instead
+//! keeps every task it owns pinned to the thread that created it, which
+//! makes it possible to spawn futures that hold `Rc`, `RefCell`, or other
+//! non-`Send` state.
+//!
+//! [`spawn`]: crate::spawn
+
+use crate::runtime::task::{self, JoinHandle};
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::rc::Rc;
+use std::task::{Context, Poll, Waker};
+
+thread_local! {
+    static CURRENT: RefCell<Option<Rc<Inner>>> = RefCell::new(None);
+}
+
+/// A set of tasks which are executed on the same thread.
+///
+/// A `LocalSet` is needed to run `!Send` futures, as produced by
+/// [`spawn_local`]. Tasks spawned onto a `LocalSet` never migrate to another
+/// thread: they are only polled while the owning `LocalSet` is being driven,
+/// either by [`LocalSet::run_until`] or [`LocalSet::block_on`].
+///
+/// Dropping a `LocalSet` drops every task that has not yet completed.
+#[derive(Debug)]
+pub struct LocalSet {
+    inner: Rc<Inner>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    /// Tasks that are ready to be polled, in FIFO order.
+    queue: RefCell<VecDeque<task::LocalTask>>,
+}
+
+impl LocalSet {
+    /// Create a new, empty `LocalSet`.
+    pub fn new() -> LocalSet {
+        LocalSet {
+            inner: Rc::new(Inner {
+                queue: RefCell::new(VecDeque::new()),
+            }),
+        }
+    }
+
+    /// Spawn a `!Send` future onto this `LocalSet`.
+    ///
+    /// Unlike [`spawn_local`], this can be called from outside of
+    /// [`run_until`](LocalSet::run_until), since the target set is explicit.
+    pub fn spawn_local<F>(&self, future: F) -> JoinHandle<F::Output>
+    where
+        F: Future + 'static,
+        F::Output: 'static,
+    {
+        self.inner.spawn(future)
+    }
+
+    /// Run a future to completion on the current thread, polling the tasks
+    /// spawned onto this `LocalSet` alongside it.
+    ///
+    /// This is the same as [`run_until`](LocalSet::run_until): it returns as
+    /// soon as `future` resolves. Tasks that have not completed by then stay
+    /// in the set; they are polled again by a later call to `block_on` or
+    /// `run_until`, and dropped along with the `LocalSet`.
+    pub fn block_on<F>(&self, future: F) -> F::Output
+    where
+        F: Future,
+    {
+        self.run_until(future)
+    }
+
+    /// Run a future to completion, cooperatively polling the local task
+    /// queue alongside it on the current thread.
+    ///
+    /// This enters the `LocalSet`'s context for the duration of the call, so
+    /// [`spawn_local`] can be used both from within `future` and from the
+    /// tasks it spawns.
+    pub fn run_until<F>(&self, future: F) -> F::Output
+    where
+        F: Future,
+    {
+        let _guard = self.enter();
+        let inner = self.inner.clone();
+
+        task::block_on_local(future, move |waker| inner.tick(waker))
+    }
+
+    fn enter(&self) -> impl Drop + '_ {
+        struct Guard<'a>(&'a LocalSet, Option<Rc<Inner>>);
+
+        impl Drop for Guard<'_> {
+            fn drop(&mut self) {
+                CURRENT.with(|current| *current.borrow_mut() = self.1.take());
+            }
+        }
+
+        let prev = CURRENT.with(|current| current.borrow_mut().replace(self.inner.clone()));
+        Guard(self, prev)
+    }
+}
+
+impl Default for LocalSet {
+    fn default() -> Self {
+        LocalSet::new()
+    }
+}
+
+impl Inner {
+    fn spawn<F>(self: &Rc<Self>, future: F) -> JoinHandle<F::Output>
+    where
+        F: Future + 'static,
+        F::Output: 'static,
+    {
+        let (task, handle) = task::LocalTask::new(future);
+        self.queue.borrow_mut().push_back(task);
+        handle
+    }
+
+    /// Poll every task currently sitting in the ready queue once, requeuing
+    /// any task that is not yet complete.
+    ///
+    /// `waker` is shared with the rest of the set's tick loop (see
+    /// [`task::block_on_local`]), so a task that's still pending after this
+    /// call can still wake the loop later instead of being stranded until
+    /// the next unrelated wake-up.
+    ///
+    /// Returns whether this round made progress: either a task completed, or
+    /// a task's poll spawned new work onto the queue that hasn't been polled
+    /// yet. A task merely staying `Pending` is not progress on its own:
+    /// reporting it as such is what made the previous driver spin at 100%
+    /// CPU the moment any task was merely waiting on something, since the
+    /// queue was never empty.
+    fn tick(&self, waker: &Waker) -> bool {
+        let batch: VecDeque<_> = self.queue.borrow_mut().drain(..).collect();
+
+        if batch.is_empty() {
+            return false;
+        }
+
+        let mut cx = Context::from_waker(waker);
+        let mut progressed = false;
+        let mut requeued = 0;
+
+        for mut task in batch {
+            match task.poll_local(&mut cx) {
+                Poll::Ready(()) => progressed = true,
+                Poll::Pending => {
+                    requeued += 1;
+                    self.queue.borrow_mut().push_back(task);
+                }
+            }
+        }
+
+        // A task's poll may call `spawn_local`, pushing new work onto this
+        // same queue; that lands after everything requeued above, so
+        // anything beyond `requeued` is a fresh task that hasn't been
+        // polled this round yet and shouldn't wait for an unrelated wake-up.
+        progressed || self.queue.borrow().len() > requeued
+    }
+}
+
+/// Spawn a `!Send` future onto the currently running [`LocalSet`].
+///
+/// # Panics
+///
+/// This function panics if called outside of [`LocalSet::run_until`] or
+/// [`LocalSet::block_on`].
+pub fn spawn_local<F>(future: F) -> JoinHandle<F::Output>
+where
+    F: Future + 'static,
+    F::Output: 'static,
+{
+    CURRENT.with(|current| match &*current.borrow() {
+        Some(inner) => inner.spawn(future),
+        None => panic!("`spawn_local` called from outside of a `LocalSet`"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::pin::Pin;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    #[test]
+    fn runs_spawned_task_to_completion() {
+        let local = LocalSet::new();
+        let handle = local.spawn_local(async { 7 });
+        let result = local.block_on(async move { handle.await });
+        assert_eq!(result.unwrap(), 7);
+    }
+
+    /// A task that spawns another task from inside its own poll (without
+    /// either task ever completing in that same round) must still have the
+    /// new task polled promptly, not stranded until an unrelated wake-up.
+    #[test]
+    fn nested_spawn_local_is_not_stranded() {
+        let local = LocalSet::new();
+
+        let outer = local.spawn_local(async {
+            let inner = spawn_local(async { 99 });
+            inner.await.unwrap()
+        });
+
+        let result = local.block_on(async move { outer.await });
+        assert_eq!(result.unwrap(), 99);
+    }
+
+    /// Regression test for a driver that busy-spun on a `noop_waker` and
+    /// treated "the queue was non-empty" as "progress was made": as long as
+    /// anything was pending it never parked, burning 100% CPU instead of
+    /// waiting to be woken.
+    #[test]
+    fn block_on_parks_instead_of_busy_spinning() {
+        struct WakeFromAnotherThread {
+            polls: Arc<AtomicUsize>,
+            fired: bool,
+        }
+
+        impl Future for WakeFromAnotherThread {
+            type Output = ();
+
+            fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+                self.polls.fetch_add(1, Ordering::SeqCst);
+
+                if !self.fired {
+                    self.fired = true;
+                    let waker = cx.waker().clone();
+                    std::thread::spawn(move || {
+                        std::thread::sleep(std::time::Duration::from_millis(50));
+                        waker.wake();
+                    });
+                    Poll::Pending
+                } else {
+                    Poll::Ready(())
+                }
+            }
+        }
+
+        let polls = Arc::new(AtomicUsize::new(0));
+        let local = LocalSet::new();
+        local.block_on(WakeFromAnotherThread {
+            polls: polls.clone(),
+            fired: false,
+        });
+
+        // A busy-spinning driver would have polled millions of times during
+        // the 50ms sleep instead of parking until woken.
+        let count = polls.load(Ordering::SeqCst);
+        assert!(count < 100, "polled {} times, driver is busy-spinning", count);
+    }
+}
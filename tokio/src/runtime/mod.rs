@@ -158,6 +158,11 @@ pub use self::global::spawn;
 
 mod io;
 
+#[cfg(feature = "rt-current-thread")]
+mod local;
+#[cfg(feature = "rt-current-thread")]
+pub use self::local::{spawn_local, LocalSet};
+
 mod park;
 pub use self::park::{Park, Unpark};
 
@@ -177,9 +182,13 @@ mod timer;
 pub(crate) mod thread_pool;
 #[cfg(feature = "rt-full")]
 use self::thread_pool::ThreadPool;
+#[cfg(feature = "rt-full")]
+pub use self::thread_pool::RuntimeMetrics;
 
 #[cfg(feature = "blocking")]
 use std::future::Future;
+#[cfg(feature = "blocking")]
+use std::time::{Duration, Instant};
 
 /// The Tokio runtime, includes a reactor as well as an executor for running
 /// tasks.
@@ -202,7 +211,15 @@ use std::future::Future;
 /// that reactor will no longer function. Calling any method on them will
 /// result in an error.
 ///
+/// The time this takes is unbounded and, for this reason, dropping may not
+/// be appropriate in all cases, e.g. as part of a request-handling path
+/// that is itself subject to a deadline. [`shutdown_timeout`] and
+/// [`shutdown_background`] give the caller control over how long the drain
+/// is allowed to run before the remaining work is abandoned.
+///
 /// [mod]: index.html
+/// [`shutdown_timeout`]: Runtime::shutdown_timeout
+/// [`shutdown_background`]: Runtime::shutdown_background
 /// [`new`]: #method.new
 /// [`Builder`]: struct.Builder.html
 /// [`tokio::run`]: fn.run.html
@@ -238,6 +255,24 @@ enum Kind {
     ThreadPool(ThreadPool),
 }
 
+impl Kind {
+    /// Signal the executor to stop accepting new work and abandon any task
+    /// that has not completed by the time `timeout` elapses.
+    ///
+    /// A `None` timeout means "wait forever", matching the blocking behavior
+    /// of dropping the `Runtime` outright.
+    #[cfg(feature = "blocking")]
+    fn shutdown(&mut self, timeout: Option<Duration>) {
+        match self {
+            Kind::Shell => {}
+            #[cfg(feature = "rt-current-thread")]
+            Kind::CurrentThread(exec) => exec.shutdown(timeout),
+            #[cfg(feature = "rt-full")]
+            Kind::ThreadPool(exec) => exec.shutdown(timeout),
+        }
+    }
+}
+
 impl Runtime {
     /// Create a new runtime instance with default configuration values.
     ///
@@ -307,6 +342,11 @@ impl Runtime {
     ///
     /// This function panics if the spawn fails. Failure occurs if the executor
     /// is currently at capacity and is unable to spawn a new future.
+    ///
+    /// The spawned future must be `Send`. To spawn a `!Send` future, such as
+    /// one holding an `Rc` or `RefCell`, onto a current-thread runtime
+    /// instead, use [`LocalSet::spawn_local`] or the free function
+    /// [`spawn_local`].
     #[cfg(feature = "rt-current-thread")]
     pub fn spawn<F>(&self, future: F) -> JoinHandle<F::Output>
     where
@@ -349,6 +389,84 @@ impl Runtime {
         })
     }
 
+    /// Shutdown the runtime, waiting at most `duration` for all spawned work
+    /// to stop.
+    ///
+    /// Usually, dropping a `Runtime` blocks the current thread until all
+    /// spawned work has completed, however `shutdown_timeout` bounds this
+    /// wait to `duration`. If `duration` elapses before all work is
+    /// drained, outstanding futures and the reactor are forcibly dropped
+    /// on a background thread, and this function returns immediately.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tokio::runtime::Runtime;
+    /// use std::time::Duration;
+    ///
+    /// let mut rt = Runtime::new().unwrap();
+    ///
+    /// rt.block_on(async move {
+    ///     println!("hello");
+    /// });
+    ///
+    /// rt.shutdown_timeout(Duration::from_millis(100));
+    /// ```
+    #[cfg(feature = "blocking")]
+    pub fn shutdown_timeout(mut self, duration: Duration) {
+        let deadline = Instant::now() + duration;
+
+        self.kind.shutdown(Some(duration));
+        self.blocking_pool
+            .shutdown(Some(deadline.saturating_duration_since(Instant::now())));
+    }
+
+    /// Shutdown the runtime, without waiting for any spawned work to stop.
+    ///
+    /// This can be used if you don't want to wait for any spawned tasks to
+    /// shutdown, regardless of whether they exit normally or not. Note that
+    /// this will not forcefully terminate tasks in the middle of synchronous
+    /// code, so they could still run for an arbitrarily long time; they will
+    /// only stop at the next `.await` point.
+    ///
+    /// Unlike dropping the `Runtime`, this method is non-blocking; it hands
+    /// the shutdown work off to a detached thread and returns immediately.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tokio::runtime::Runtime;
+    ///
+    /// let mut rt = Runtime::new().unwrap();
+    ///
+    /// rt.block_on(async move {
+    ///     println!("hello");
+    /// });
+    ///
+    /// rt.shutdown_background();
+    /// ```
+    #[cfg(feature = "blocking")]
+    pub fn shutdown_background(self) {
+        std::thread::spawn(move || self.shutdown_timeout(Duration::from_nanos(0)));
+    }
+
+    /// Return a handle to this runtime's work-stealing thread pool
+    /// instrumentation, if it has one.
+    ///
+    /// The returned [`RuntimeMetrics`] is cheap to clone and can be handed
+    /// to external monitoring code, which can poll it at its own cadence
+    /// without holding any reference into the pool's internals.
+    ///
+    /// Returns `None` for a shell or current-thread runtime, since neither
+    /// has worker threads to steal between.
+    #[cfg(feature = "rt-full")]
+    pub fn metrics(&self) -> Option<RuntimeMetrics> {
+        match &self.kind {
+            Kind::ThreadPool(exec) => Some(exec.metrics()),
+            _ => None,
+        }
+    }
+
     /// Return a handle to the runtime's spawner.
     ///
     /// The returned handle can be used to spawn tasks that run on this runtime.
@@ -0,0 +1,402 @@
+//! The task harness shared by the `current_thread` and `thread_pool`
+//! executors.
+//!
+//! Spawning a future wraps it in a [`Notified`], which owns the future and is
+//! polled by the scheduler, paired with the [`JoinHandle`] the caller uses
+//! to observe its output. Both halves share the same reference-counted
+//! atomic state, which is what lets [`JoinHandle::abort`] cancel a
+//! task regardless of which worker currently owns it: the abort only ever
+//! flips a bit, and the scheduler consults that bit the next time it is
+//! about to poll the task.
+
+use crate::sync::oneshot;
+
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::task::{Context, Poll, Waker};
+
+/// An owned permission to join on a task (await its termination).
+///
+/// This is returned by [`Runtime::spawn`] and [`spawn_local`]. Awaiting it
+/// yields the task's output, wrapped in a `Result` that reports a
+/// [`JoinError`] if the task panicked or was [cancelled](JoinHandle::abort).
+/// A panic in a task spawned with [`spawn_local`] is not caught: it unwinds
+/// out of the `LocalSet` call driving that task.
+///
+/// Dropping a `JoinHandle` does *not* cancel the task it refers to; the task
+/// keeps running in the background. To cancel it, call [`abort`].
+///
+/// [`Runtime::spawn`]: crate::runtime::Runtime::spawn
+/// [`spawn_local`]: crate::runtime::spawn_local
+/// [`abort`]: JoinHandle::abort
+#[derive(Debug)]
+pub struct JoinHandle<T> {
+    raw: Arc<Shared>,
+    rx: oneshot::Receiver<Result<T, Panicked>>,
+}
+
+/// Task failed to complete successfully.
+///
+/// Awaiting a [`JoinHandle`] returns this error if the spawned task panicked
+/// or was cancelled via [`JoinHandle::abort`] before it finished running.
+pub struct JoinError {
+    repr: Repr,
+}
+
+enum Repr {
+    Cancelled,
+    Panic,
+}
+
+/// Internal marker sent through the completion channel when the spawned
+/// future panics while being polled, as opposed to completing or being
+/// cancelled.
+#[derive(Debug)]
+pub(crate) struct Panicked;
+
+/// State shared between a `JoinHandle` and the scheduler driving the task it
+/// refers to.
+#[derive(Debug, Default)]
+struct Shared {
+    /// Set by [`JoinHandle::abort`]. Checked by the scheduler immediately
+    /// before each poll of the task's future; once observed, the scheduler
+    /// drops the future without polling it again and reports cancellation
+    /// through the completion channel instead.
+    abort_requested: AtomicBool,
+}
+
+impl<T> JoinHandle<T> {
+    pub(crate) fn new(raw: Arc<Shared>, rx: oneshot::Receiver<Result<T, Panicked>>) -> Self {
+        JoinHandle { raw, rx }
+    }
+
+    /// Abort the task associated with this `JoinHandle`.
+    ///
+    /// Aborting a task that has already completed has no effect.
+    ///
+    /// When this call returns, the task *may* still be running for a short
+    /// while if it is currently being polled on another worker thread; the
+    /// cancellation only takes effect the next time the scheduler is about
+    /// to poll it. Once cancelled, awaiting this handle resolves to a
+    /// [`JoinError`] for which [`JoinError::is_cancelled`] returns `true`.
+    pub fn abort(&self) {
+        self.raw.abort_requested.store(true, Ordering::SeqCst);
+    }
+}
+
+impl<T> Future for JoinHandle<T> {
+    type Output = Result<T, JoinError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+
+        // `rx` is authoritative: if the task already sent its output before
+        // `abort` was called (a documented no-op in that case), the real
+        // output must win. Only fall back to reporting cancellation when the
+        // channel itself has nothing to say yet.
+        match Pin::new(&mut this.rx).poll(cx) {
+            Poll::Ready(Ok(Ok(output))) => Poll::Ready(Ok(output)),
+            Poll::Ready(Ok(Err(Panicked))) => Poll::Ready(Err(JoinError::panic())),
+            Poll::Ready(Err(_)) => Poll::Ready(Err(JoinError::cancelled())),
+            Poll::Pending if this.raw.abort_requested.load(Ordering::SeqCst) => {
+                Poll::Ready(Err(JoinError::cancelled()))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+// ===== impl JoinError =====
+
+impl JoinError {
+    fn cancelled() -> Self {
+        JoinError {
+            repr: Repr::Cancelled,
+        }
+    }
+
+    fn panic() -> Self {
+        JoinError { repr: Repr::Panic }
+    }
+
+    /// Returns `true` if the task was cancelled via [`JoinHandle::abort`],
+    /// as opposed to having panicked.
+    pub fn is_cancelled(&self) -> bool {
+        matches!(self.repr, Repr::Cancelled)
+    }
+
+    /// Returns `true` if the task panicked while being polled.
+    pub fn is_panic(&self) -> bool {
+        matches!(self.repr, Repr::Panic)
+    }
+}
+
+impl fmt::Debug for JoinError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.repr {
+            Repr::Cancelled => write!(fmt, "JoinError::Cancelled"),
+            Repr::Panic => write!(fmt, "JoinError::Panic"),
+        }
+    }
+}
+
+impl fmt::Display for JoinError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.repr {
+            Repr::Cancelled => write!(fmt, "task was cancelled"),
+            Repr::Panic => write!(fmt, "task panicked"),
+        }
+    }
+}
+
+impl std::error::Error for JoinError {}
+
+/// Spawn `future`, returning the `Notified` handle the scheduler uses to
+/// drive it to completion and the `JoinHandle` the caller awaits.
+///
+/// Shared between `current_thread` and `thread_pool`: both call this to
+/// build the harness, then poll the returned `Notified` from their own
+/// run queues, checking `Notified::is_abort_requested()` immediately before
+/// each poll so an abort is observed no matter which worker currently owns
+/// the task.
+pub(crate) fn spawn<F>(future: F) -> (Notified<F>, JoinHandle<F::Output>)
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    let shared = Arc::new(Shared::default());
+    let (tx, rx) = oneshot::channel();
+
+    let notified = Notified {
+        shared: shared.clone(),
+        future: Box::pin(future),
+        tx: Some(tx),
+    };
+
+    (notified, JoinHandle::new(shared, rx))
+}
+
+/// The scheduler-facing half of a spawned task.
+///
+/// A worker polls this directly. Only the abort state is reference counted
+/// between it and the joiner-facing [`JoinHandle`]; the future itself is
+/// owned by the `Notified` alone, since only one worker drives a given
+/// task's future at a time (even across a cross-worker move, the `Notified`
+/// itself simply relocates).
+pub(crate) struct Notified<F: Future> {
+    shared: Arc<Shared>,
+    future: Pin<Box<F>>,
+    tx: Option<oneshot::Sender<Result<F::Output, Panicked>>>,
+}
+
+impl<F: Future> Notified<F> {
+    /// Whether [`JoinHandle::abort`] has been called for this task.
+    ///
+    /// The scheduler must check this immediately before each poll; if it
+    /// returns `true` the task should be dropped without polling rather than
+    /// resumed.
+    pub(crate) fn is_abort_requested(&self) -> bool {
+        self.shared.abort_requested.load(Ordering::SeqCst)
+    }
+
+    /// Poll the wrapped future once, reporting completion through the
+    /// `JoinHandle`'s channel.
+    ///
+    /// Returns `Poll::Ready` once the future has completed (successfully or
+    /// otherwise) and no further polling is necessary.
+    pub(crate) fn poll(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        if self.is_abort_requested() {
+            // Dropping `tx` without sending completes the `JoinHandle`'s
+            // receiver with an error, which is mapped to a cancelled
+            // `JoinError`.
+            self.tx = None;
+            return Poll::Ready(());
+        }
+
+        let polled = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            self.future.as_mut().poll(cx)
+        }));
+
+        match polled {
+            Ok(Poll::Ready(output)) => {
+                if let Some(tx) = self.tx.take() {
+                    let _ = tx.send(Ok(output));
+                }
+                Poll::Ready(())
+            }
+            Ok(Poll::Pending) => Poll::Pending,
+            Err(_) => {
+                if let Some(tx) = self.tx.take() {
+                    let _ = tx.send(Err(Panicked));
+                }
+                Poll::Ready(())
+            }
+        }
+    }
+}
+
+/// A `!Send` task spawned onto a [`LocalSet`](crate::runtime::LocalSet).
+///
+/// Structurally identical to [`Notified`], except the wrapped future (and
+/// therefore the task itself) never crosses a thread boundary, so no `Send`
+/// bound is required.
+pub(crate) struct LocalTask {
+    shared: Arc<Shared>,
+    future: Pin<Box<dyn Future<Output = ()>>>,
+}
+
+impl LocalTask {
+    pub(crate) fn new<F>(future: F) -> (LocalTask, JoinHandle<F::Output>)
+    where
+        F: Future + 'static,
+        F::Output: 'static,
+    {
+        let shared = Arc::new(Shared::default());
+        let (tx, rx) = oneshot::channel();
+        let mut tx = Some(tx);
+
+        let wrapped = async move {
+            let output = future.await;
+            if let Some(tx) = tx.take() {
+                let _ = tx.send(Ok(output));
+            }
+        };
+
+        let task = LocalTask {
+            shared: shared.clone(),
+            future: Box::pin(wrapped),
+        };
+
+        (task, JoinHandle::new(shared, rx))
+    }
+
+    /// Poll the task once. Called by [`LocalSet`](crate::runtime::LocalSet)
+    /// from its own cooperative tick loop.
+    ///
+    /// `cx` carries the waker shared with the rest of the `LocalSet`'s tick
+    /// loop, so that a wake-up coming from inside this task (e.g. a channel
+    /// or timer becoming ready) un-parks the thread driving the set instead
+    /// of being silently swallowed.
+    pub(crate) fn poll_local(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        if self.shared.abort_requested.load(Ordering::SeqCst) {
+            return Poll::Ready(());
+        }
+
+        self.future.as_mut().poll(cx)
+    }
+}
+
+impl fmt::Debug for LocalTask {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("LocalTask").finish()
+    }
+}
+
+/// Drive `future` to completion, calling `tick_local` between polls so the
+/// caller's local task queue keeps making progress in the meantime.
+///
+/// This is the cooperative driver behind [`LocalSet::run_until`] and
+/// [`LocalSet::block_on`](crate::runtime::LocalSet::block_on). `future` and
+/// every task in the local queue share one [`Waker`]: whichever of them wakes
+/// first un-parks this thread, so the loop blocks on a condition variable
+/// instead of spinning whenever nothing is immediately runnable.
+///
+/// Note this only arbitrates between `future` and the local queue; it has no
+/// way to hook into the I/O or timer driver's own wake-ups, so a local task
+/// that's purely waiting on the reactor still depends on the reactor itself
+/// waking this set's shared waker.
+pub(crate) fn block_on_local<F>(future: F, mut tick_local: impl FnMut(&Waker) -> bool) -> F::Output
+where
+    F: Future,
+{
+    futures_util::pin_mut!(future);
+
+    let signal = Signal::new();
+    let waker = futures_util::task::waker(signal.clone());
+    let mut cx = Context::from_waker(&waker);
+
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+
+        if !tick_local(&waker) {
+            signal.wait();
+        }
+    }
+}
+
+/// A [`Waker`] backed by a condition variable, used to park
+/// [`block_on_local`]'s driving thread instead of having it busy-spin while
+/// neither the top-level future nor any local task is immediately runnable.
+struct Signal {
+    woken: Mutex<bool>,
+    condvar: Condvar,
+}
+
+impl Signal {
+    fn new() -> Arc<Signal> {
+        Arc::new(Signal {
+            woken: Mutex::new(false),
+            condvar: Condvar::new(),
+        })
+    }
+
+    /// Block until [`wake_by_ref`](futures_util::task::ArcWake::wake_by_ref)
+    /// has been called at least once since the last call to `wait`.
+    fn wait(&self) {
+        let mut woken = self.woken.lock().unwrap();
+        while !*woken {
+            woken = self.condvar.wait(woken).unwrap();
+        }
+        *woken = false;
+    }
+}
+
+impl futures_util::task::ArcWake for Signal {
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        *arc_self.woken.lock().unwrap() = true;
+        arc_self.condvar.notify_one();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn abort_after_completion_does_not_discard_output() {
+        let (mut notified, mut handle) = spawn(async { 42 });
+
+        let waker = futures_util::task::noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        assert_eq!(notified.poll(&mut cx), Poll::Ready(()));
+
+        // Documented as a no-op, since the task already finished.
+        handle.abort();
+
+        match Pin::new(&mut handle).poll(&mut cx) {
+            Poll::Ready(Ok(output)) => assert_eq!(output, 42),
+            other => panic!("expected Ok(42), got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn abort_before_completion_is_reported_as_cancelled() {
+        let (mut notified, mut handle) = spawn(std::future::pending::<()>());
+
+        handle.abort();
+
+        let waker = futures_util::task::noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        assert_eq!(notified.poll(&mut cx), Poll::Ready(()));
+
+        match Pin::new(&mut handle).poll(&mut cx) {
+            Poll::Ready(Err(e)) => assert!(e.is_cancelled()),
+            other => panic!("expected a cancelled JoinError, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,171 @@
+//! Instrumentation counters for the work-stealing thread pool.
+//!
+//! Each worker is meant to own a [`WorkerMetrics`] and bump its counters with
+//! `Relaxed` atomics on the scheduling hot path (pushing a task onto its
+//! local queue, stealing from a sibling, parking, ...), so the cost of
+//! instrumentation is a handful of unsynchronized stores per scheduling
+//! decision, whether or not anyone is reading the counters.
+//! [`RuntimeMetrics`] is the cloneable, read-only view handed out to callers
+//! via [`Runtime::metrics`](crate::runtime::Runtime::metrics).
+//!
+//! `worker.rs`/`mod.rs` (the scheduler itself) are not part of this checkout,
+//! so the `inc_*`/`set_*` calls on the hot path described above aren't wired
+//! up here; this module only provides the counters and their accessors,
+//! ready for the scheduler to drive.
+
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering::Relaxed};
+use std::sync::Arc;
+
+/// A snapshot-capable handle onto a running thread pool's per-worker
+/// counters.
+///
+/// Cloning a `RuntimeMetrics` is cheap: it's just an `Arc` bump, and every
+/// clone reads the same live counters, so external monitoring code can poll
+/// it on its own schedule without holding a reference into the pool itself.
+#[derive(Clone, Debug)]
+pub struct RuntimeMetrics {
+    workers: Arc<[WorkerMetrics]>,
+}
+
+/// Per-worker counters, maintained with relaxed atomics on the scheduling
+/// hot path.
+#[derive(Debug, Default)]
+pub(crate) struct WorkerMetrics {
+    /// Tasks pushed onto this worker's local run queue, whether newly
+    /// spawned or woken up.
+    tasks_scheduled: AtomicU64,
+
+    /// Tasks this worker took from a sibling's queue because its own was
+    /// empty.
+    tasks_stolen: AtomicU64,
+
+    /// Attempts made to steal from a sibling, successful or not.
+    steal_attempts: AtomicU64,
+
+    /// Current number of tasks sitting in this worker's local queue.
+    local_queue_depth: AtomicUsize,
+
+    /// Number of times this worker has parked waiting for work.
+    park_count: AtomicU64,
+
+    /// Number of times this worker has been unparked.
+    unpark_count: AtomicU64,
+
+    /// Total tasks spawned directly onto this worker (as opposed to moved
+    /// here by stealing).
+    tasks_spawned: AtomicU64,
+}
+
+impl WorkerMetrics {
+    pub(crate) fn inc_tasks_scheduled(&self) {
+        self.tasks_scheduled.fetch_add(1, Relaxed);
+    }
+
+    pub(crate) fn inc_tasks_stolen(&self) {
+        self.tasks_stolen.fetch_add(1, Relaxed);
+    }
+
+    pub(crate) fn inc_steal_attempts(&self) {
+        self.steal_attempts.fetch_add(1, Relaxed);
+    }
+
+    pub(crate) fn set_local_queue_depth(&self, depth: usize) {
+        self.local_queue_depth.store(depth, Relaxed);
+    }
+
+    pub(crate) fn inc_park_count(&self) {
+        self.park_count.fetch_add(1, Relaxed);
+    }
+
+    pub(crate) fn inc_unpark_count(&self) {
+        self.unpark_count.fetch_add(1, Relaxed);
+    }
+
+    pub(crate) fn inc_tasks_spawned(&self) {
+        self.tasks_spawned.fetch_add(1, Relaxed);
+    }
+}
+
+impl RuntimeMetrics {
+    pub(crate) fn new(workers: Arc<[WorkerMetrics]>) -> Self {
+        RuntimeMetrics { workers }
+    }
+
+    /// The number of worker threads backing the pool this handle was
+    /// obtained from.
+    pub fn num_workers(&self) -> usize {
+        self.workers.len()
+    }
+
+    /// Tasks scheduled onto `worker`'s local queue so far.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `worker` is greater than or equal to [`num_workers`](Self::num_workers).
+    pub fn worker_tasks_scheduled(&self, worker: usize) -> u64 {
+        self.workers[worker].tasks_scheduled.load(Relaxed)
+    }
+
+    /// Tasks `worker` has stolen from a sibling worker's queue.
+    pub fn worker_tasks_stolen(&self, worker: usize) -> u64 {
+        self.workers[worker].tasks_stolen.load(Relaxed)
+    }
+
+    /// Steal attempts `worker` has made, successful or not.
+    pub fn worker_steal_attempts(&self, worker: usize) -> u64 {
+        self.workers[worker].steal_attempts.load(Relaxed)
+    }
+
+    /// The current depth of `worker`'s local run queue.
+    pub fn worker_local_queue_depth(&self, worker: usize) -> usize {
+        self.workers[worker].local_queue_depth.load(Relaxed)
+    }
+
+    /// The number of times `worker` has parked.
+    pub fn worker_park_count(&self, worker: usize) -> u64 {
+        self.workers[worker].park_count.load(Relaxed)
+    }
+
+    /// The number of times `worker` has been unparked.
+    pub fn worker_unpark_count(&self, worker: usize) -> u64 {
+        self.workers[worker].unpark_count.load(Relaxed)
+    }
+
+    /// Total tasks spawned across every worker in the pool.
+    pub fn total_tasks_spawned(&self) -> u64 {
+        self.workers
+            .iter()
+            .map(|w| w.tasks_spawned.load(Relaxed))
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counters_read_back_what_was_incremented() {
+        let workers: Arc<[WorkerMetrics]> = Arc::new([WorkerMetrics::default(), WorkerMetrics::default()]);
+        let metrics = RuntimeMetrics::new(workers.clone());
+
+        workers[0].inc_tasks_scheduled();
+        workers[0].inc_tasks_scheduled();
+        workers[0].inc_tasks_stolen();
+        workers[0].inc_steal_attempts();
+        workers[0].set_local_queue_depth(3);
+        workers[0].inc_park_count();
+        workers[0].inc_unpark_count();
+        workers[0].inc_tasks_spawned();
+        workers[1].inc_tasks_spawned();
+
+        assert_eq!(metrics.num_workers(), 2);
+        assert_eq!(metrics.worker_tasks_scheduled(0), 2);
+        assert_eq!(metrics.worker_tasks_stolen(0), 1);
+        assert_eq!(metrics.worker_steal_attempts(0), 1);
+        assert_eq!(metrics.worker_local_queue_depth(0), 3);
+        assert_eq!(metrics.worker_park_count(0), 1);
+        assert_eq!(metrics.worker_unpark_count(0), 1);
+        assert_eq!(metrics.total_tasks_spawned(), 2);
+    }
+}
@@ -57,6 +57,7 @@ use core::task::Poll::{Pending, Ready};
 use core::task::{Context, Poll};
 use fnv::FnvHashMap;
 use futures_util::future::poll_fn;
+use std::mem;
 use std::ops;
 use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering::SeqCst;
@@ -122,6 +123,21 @@ pub mod error {
     }
 
     impl<T: fmt::Debug> ::std::error::Error for SendError<T> {}
+
+    /// Error produced when checking for a new value fails because the
+    /// `Sender` half has been dropped.
+    #[derive(Debug)]
+    pub struct RecvError(pub(crate) ());
+
+    // ===== impl RecvError =====
+
+    impl fmt::Display for RecvError {
+        fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(fmt, "channel closed")
+        }
+    }
+
+    impl ::std::error::Error for RecvError {}
 }
 
 #[derive(Debug)]
@@ -231,10 +247,48 @@ impl<T> Receiver<T> {
     /// assert_eq!(*rx.get_ref(), "hello");
     /// ```
     pub fn get_ref(&self) -> Ref<'_, T> {
+        self.borrow()
+    }
+
+    /// Returns a reference to the most recently sent value without marking
+    /// it as seen.
+    ///
+    /// Outstanding borrows hold a read lock. This means that long lived
+    /// borrows could cause the send half to block. It is recommended to
+    /// keep the borrow as short lived as possible.
+    ///
+    /// Unlike [`recv_ref`](Receiver::recv_ref) or
+    /// [`borrow_and_update`](Receiver::borrow_and_update), this never
+    /// advances this receiver's observed version, so a later call to
+    /// `recv`/`recv_ref` will still report the value as new, as long as it
+    /// hasn't already been consumed some other way.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tokio::sync::watch;
+    ///
+    /// let (_, rx) = watch::channel("hello");
+    /// assert_eq!(*rx.borrow(), "hello");
+    /// ```
+    pub fn borrow(&self) -> Ref<'_, T> {
         let inner = self.shared.value.read().unwrap();
         Ref { inner }
     }
 
+    /// Returns a reference to the most recently sent value, marking it as
+    /// seen.
+    ///
+    /// This is equivalent to [`borrow`](Receiver::borrow), except that it
+    /// also advances this receiver's observed version to the one currently
+    /// visible. A following call to `recv`/`recv_ref` will then block until
+    /// a genuinely new value is broadcast, rather than immediately
+    /// returning the value borrowed here again.
+    pub fn borrow_and_update(&mut self) -> Ref<'_, T> {
+        self.ver = self.shared.version.load(SeqCst) & !CLOSED;
+        self.borrow()
+    }
+
     /// Attempts to receive the latest value sent via the channel.
     ///
     /// If a new, unobserved, value has been sent, a reference to it is
@@ -256,6 +310,36 @@ impl<T> Receiver<T> {
             None => None,
         }
     }
+
+    /// Check whether this channel contains a value that this receiver has
+    /// not yet observed, without blocking or marking it as seen.
+    ///
+    /// This is a cheap way to decide whether it's worth calling
+    /// [`recv_ref`](Receiver::recv_ref) at all.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the [`Sender`] half has been dropped and this
+    /// receiver has already observed the last value it sent, since no
+    /// further values can arrive. A value broadcast just before the
+    /// `Sender` was dropped is still reported as `Ok(true)` until observed.
+    pub fn has_changed(&self) -> Result<bool, error::RecvError> {
+        let state = self.shared.version.load(SeqCst);
+        let version = state & !CLOSED;
+
+        // Mirrors `poll_lock`'s ordering: an unobserved value always wins,
+        // even if the `Sender` went on to close the channel right after
+        // broadcasting it, since `recv_ref` would still return that value.
+        if version != self.ver {
+            return Ok(true);
+        }
+
+        if CLOSED == state & CLOSED {
+            return Err(error::RecvError(()));
+        }
+
+        Ok(false)
+    }
 }
 
 fn poll_lock<'a, T>(
@@ -351,19 +435,24 @@ impl WatchInner {
 }
 
 impl<T> Sender<T> {
-    /// Broadcast a new value via the channel, notifying all receivers.
-    pub fn broadcast(&self, value: T) -> Result<(), error::SendError<T>> {
+    /// Broadcast a new value via the channel, notifying all receivers, and
+    /// return the value it replaced.
+    ///
+    /// This is useful for config-reload style use cases, where a caller
+    /// wants to diff the old and new values, or run teardown logic on the
+    /// value being replaced.
+    pub fn broadcast(&self, value: T) -> Result<T, error::SendError<T>> {
         let shared = match self.shared.upgrade() {
             Some(shared) => shared,
             // All `Watch` handles have been canceled
             None => return Err(error::SendError { inner: value }),
         };
 
-        // Replace the value
-        {
+        // Replace the value, keeping hold of the one it replaced.
+        let old = {
             let mut lock = shared.value.write().unwrap();
-            *lock = value;
-        }
+            mem::replace(&mut *lock, value)
+        };
 
         // Update the version. 2 is used so that the CLOSED bit is not set.
         shared.version.fetch_add(2, SeqCst);
@@ -371,8 +460,24 @@ impl<T> Sender<T> {
         // Notify all watchers
         notify_all(&*shared);
 
-        // Return the old value
-        Ok(())
+        Ok(old)
+    }
+
+    /// Returns the number of receivers currently listening on this channel.
+    ///
+    /// This can be used to cheaply check for interest before doing
+    /// expensive work, without having to `.await` [`closed`](Sender::closed).
+    pub fn receiver_count(&self) -> usize {
+        match self.shared.upgrade() {
+            Some(shared) => shared.watchers.lock().unwrap().watchers.len(),
+            None => 0,
+        }
+    }
+
+    /// Returns `true` if all receivers have dropped, meaning no further
+    /// values broadcast on this channel will be observed.
+    pub fn is_closed(&self) -> bool {
+        self.shared.upgrade().is_none()
     }
 
     /// Completes when all receivers have dropped.
@@ -451,3 +556,62 @@ impl<T> Drop for Shared<T> {
         self.cancel.wake();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn borrow_does_not_mark_the_value_as_seen() {
+        let (tx, rx) = channel("hello");
+
+        tx.broadcast("world").unwrap();
+
+        assert_eq!(*rx.borrow(), "world");
+        // `borrow` must not advance the receiver's observed version, so the
+        // value is still reported as new afterward.
+        assert_eq!(rx.has_changed().unwrap(), true);
+    }
+
+    #[test]
+    fn borrow_and_update_marks_the_value_as_seen() {
+        let (tx, mut rx) = channel("hello");
+
+        tx.broadcast("world").unwrap();
+
+        assert_eq!(*rx.borrow_and_update(), "world");
+        assert_eq!(rx.has_changed().unwrap(), false);
+    }
+
+    #[test]
+    fn broadcast_returns_the_replaced_value() {
+        let (tx, _rx) = channel("a");
+
+        assert_eq!(tx.broadcast("b").unwrap(), "a");
+        assert_eq!(tx.broadcast("c").unwrap(), "b");
+    }
+
+    #[test]
+    fn has_changed_reports_an_unobserved_final_value_even_after_close() {
+        let (tx, rx) = channel("hello");
+
+        tx.broadcast("world").unwrap();
+        drop(tx);
+
+        // The final value was never observed, so it must still be reported,
+        // matching what `recv_ref` would return, rather than `Err` just
+        // because the sender happened to be dropped in the meantime.
+        assert_eq!(rx.has_changed().unwrap(), true);
+    }
+
+    #[test]
+    fn has_changed_reports_closed_once_caught_up() {
+        let (tx, mut rx) = channel("hello");
+
+        tx.broadcast("world").unwrap();
+        let _ = rx.borrow_and_update();
+        drop(tx);
+
+        assert!(rx.has_changed().is_err());
+    }
+}